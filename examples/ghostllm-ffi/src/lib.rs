@@ -1,6 +1,24 @@
+#[cfg(feature = "ffi")]
 use std::ffi::{CStr, CString};
-use std::os::raw::{c_char, c_int};
+#[cfg(feature = "ffi")]
+use std::os::raw::{c_char, c_void};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Errors produced by the GhostLLM layer.
+#[derive(Debug, Error)]
+pub enum LlmError {
+    #[error("failed to initialize GhostLLM: {0}")]
+    InitFailed(String),
+    #[error("FFI string argument was null or not valid UTF-8")]
+    InvalidUtf8,
+    #[error("failed to parse request JSON: {0}")]
+    JsonParse(#[from] serde_json::Error),
+    #[error("GhostLLM instance not initialized")]
+    NotInitialized,
+    #[error("token callback was null")]
+    MissingCallback,
+}
 
 /// Represents a chat request to the LLM
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,16 +50,16 @@ impl GhostLLM {
         }
     }
 
-    pub fn init(&mut self) -> Result<(), &'static str> {
+    pub fn init(&mut self) -> Result<(), LlmError> {
         // Simulate model initialization
         println!("Initializing GhostLLM with model: {}", self.model_path);
         self.initialized = true;
         Ok(())
     }
 
-    pub fn chat_completion(&self, request: &ChatRequest) -> Result<ChatResponse, &'static str> {
+    pub fn chat_completion(&self, request: &ChatRequest) -> Result<ChatResponse, LlmError> {
         if !self.initialized {
-            return Err("GhostLLM not initialized");
+            return Err(LlmError::NotInitialized);
         }
 
         // Simulate AI processing
@@ -53,43 +71,113 @@ impl GhostLLM {
 
         Ok(response)
     }
+
+    /// Like [`chat_completion`](Self::chat_completion), but invokes `on_token`
+    /// with each emitted token as it's produced, returning the final
+    /// aggregated response once generation stops.
+    pub fn chat_completion_stream(
+        &self,
+        request: &ChatRequest,
+        mut on_token: impl FnMut(&str),
+    ) -> Result<ChatResponse, LlmError> {
+        if !self.initialized {
+            return Err(LlmError::NotInitialized);
+        }
+
+        // Simulate AI processing, streamed one token at a time.
+        let content = format!("AI Response to: {} [temp={}]", request.prompt, request.temperature);
+        let mut tokens_used = 0u32;
+
+        for token in content.split_whitespace() {
+            if tokens_used >= request.max_tokens {
+                break;
+            }
+            on_token(token);
+            tokens_used += 1;
+        }
+
+        let finish_reason = if tokens_used >= request.max_tokens {
+            "length"
+        } else {
+            "stop"
+        };
+
+        Ok(ChatResponse {
+            content,
+            tokens_used,
+            finish_reason: finish_reason.to_string(),
+        })
+    }
 }
 
 // C FFI exports
 #[cfg(feature = "ffi")]
 pub mod ffi {
     use super::*;
+    use std::cell::RefCell;
     use std::ptr;
 
+    thread_local! {
+        static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    }
+
+    /// Records `err` as the calling thread's last error, for retrieval via
+    /// `ghostllm_last_error`.
+    fn set_last_error(err: LlmError) {
+        let message = CString::new(err.to_string())
+            .unwrap_or_else(|_| CString::new("error message contained NUL byte").unwrap());
+        LAST_ERROR.with(|slot| *slot.borrow_mut() = Some(message));
+    }
+
     /// Initialize a new GhostLLM instance
     /// Returns: Pointer to GhostLLM instance, or null on failure
+    ///
+    /// # Safety
+    /// `model_path` must be either null or a valid NUL-terminated C string.
     #[no_mangle]
-    pub extern "C" fn ghostllm_init(model_path: *const c_char) -> *mut GhostLLM {
+    pub unsafe extern "C" fn ghostllm_init(model_path: *const c_char) -> *mut GhostLLM {
         if model_path.is_null() {
+            set_last_error(LlmError::InvalidUtf8);
             return ptr::null_mut();
         }
 
         let c_str = unsafe { CStr::from_ptr(model_path) };
         let path_str = match c_str.to_str() {
             Ok(s) => s,
-            Err(_) => return ptr::null_mut(),
+            Err(_) => {
+                set_last_error(LlmError::InvalidUtf8);
+                return ptr::null_mut();
+            }
         };
 
         let mut instance = Box::new(GhostLLM::new(path_str));
         match instance.init() {
             Ok(_) => Box::into_raw(instance),
-            Err(_) => ptr::null_mut(),
+            Err(e) => {
+                set_last_error(e);
+                ptr::null_mut()
+            }
         }
     }
 
     /// Process a chat completion request
     /// Returns: JSON string response, or null on failure
+    ///
+    /// # Safety
+    /// `instance` must be either null or a pointer returned by
+    /// `ghostllm_init` that hasn't yet been passed to `ghostllm_destroy`.
+    /// `request_json` must be either null or a valid NUL-terminated C string.
     #[no_mangle]
-    pub extern "C" fn ghostllm_chat_completion(
+    pub unsafe extern "C" fn ghostllm_chat_completion(
         instance: *mut GhostLLM,
         request_json: *const c_char,
     ) -> *mut c_char {
-        if instance.is_null() || request_json.is_null() {
+        if instance.is_null() {
+            set_last_error(LlmError::NotInitialized);
+            return ptr::null_mut();
+        }
+        if request_json.is_null() {
+            set_last_error(LlmError::InvalidUtf8);
             return ptr::null_mut();
         }
 
@@ -97,33 +185,133 @@ pub mod ffi {
         let c_str = unsafe { CStr::from_ptr(request_json) };
         let json_str = match c_str.to_str() {
             Ok(s) => s,
-            Err(_) => return ptr::null_mut(),
+            Err(_) => {
+                set_last_error(LlmError::InvalidUtf8);
+                return ptr::null_mut();
+            }
         };
 
         let request: ChatRequest = match serde_json::from_str(json_str) {
             Ok(req) => req,
-            Err(_) => return ptr::null_mut(),
+            Err(e) => {
+                set_last_error(LlmError::JsonParse(e));
+                return ptr::null_mut();
+            }
         };
 
         let response = match ghostllm.chat_completion(&request) {
             Ok(resp) => resp,
-            Err(_) => return ptr::null_mut(),
+            Err(e) => {
+                set_last_error(e);
+                return ptr::null_mut();
+            }
+        };
+
+        let response_json = match serde_json::to_string(&response) {
+            Ok(json) => json,
+            Err(e) => {
+                set_last_error(LlmError::JsonParse(e));
+                return ptr::null_mut();
+            }
+        };
+
+        match CString::new(response_json) {
+            Ok(c_string) => c_string.into_raw(),
+            Err(_) => {
+                set_last_error(LlmError::InvalidUtf8);
+                ptr::null_mut()
+            }
+        }
+    }
+
+    /// Called once per emitted token by `ghostllm_chat_completion_stream`.
+    /// `token` is a NUL-terminated UTF-8 chunk valid only for the duration
+    /// of the call; `user_data` is passed through unchanged.
+    pub type TokenCallback = extern "C" fn(token: *const c_char, user_data: *mut c_void);
+
+    /// Process a chat completion request, invoking `callback` per emitted
+    /// token as it's produced.
+    /// Returns: JSON string of the final response, or null on failure. The
+    /// returned pointer must still be freed with `ghostllm_free_string`.
+    ///
+    /// # Safety
+    /// `instance` must be either null or a pointer returned by
+    /// `ghostllm_init` that hasn't yet been passed to `ghostllm_destroy`.
+    /// `request_json` must be either null or a valid NUL-terminated C string.
+    #[no_mangle]
+    pub unsafe extern "C" fn ghostllm_chat_completion_stream(
+        instance: *mut GhostLLM,
+        request_json: *const c_char,
+        callback: Option<TokenCallback>,
+        user_data: *mut c_void,
+    ) -> *mut c_char {
+        if instance.is_null() {
+            set_last_error(LlmError::NotInitialized);
+            return ptr::null_mut();
+        }
+        if request_json.is_null() {
+            set_last_error(LlmError::InvalidUtf8);
+            return ptr::null_mut();
+        }
+        let Some(callback) = callback else {
+            set_last_error(LlmError::MissingCallback);
+            return ptr::null_mut();
+        };
+
+        let ghostllm = unsafe { &*instance };
+        let c_str = unsafe { CStr::from_ptr(request_json) };
+        let json_str = match c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => {
+                set_last_error(LlmError::InvalidUtf8);
+                return ptr::null_mut();
+            }
+        };
+
+        let request: ChatRequest = match serde_json::from_str(json_str) {
+            Ok(req) => req,
+            Err(e) => {
+                set_last_error(LlmError::JsonParse(e));
+                return ptr::null_mut();
+            }
+        };
+
+        let response = match ghostllm.chat_completion_stream(&request, |token| {
+            if let Ok(c_token) = CString::new(token) {
+                callback(c_token.as_ptr(), user_data);
+            }
+        }) {
+            Ok(resp) => resp,
+            Err(e) => {
+                set_last_error(e);
+                return ptr::null_mut();
+            }
         };
 
         let response_json = match serde_json::to_string(&response) {
             Ok(json) => json,
-            Err(_) => return ptr::null_mut(),
+            Err(e) => {
+                set_last_error(LlmError::JsonParse(e));
+                return ptr::null_mut();
+            }
         };
 
         match CString::new(response_json) {
             Ok(c_string) => c_string.into_raw(),
-            Err(_) => ptr::null_mut(),
+            Err(_) => {
+                set_last_error(LlmError::InvalidUtf8);
+                ptr::null_mut()
+            }
         }
     }
 
     /// Free a string returned by ghostllm_chat_completion
+    ///
+    /// # Safety
+    /// `s` must be either null or a pointer previously returned by one of
+    /// this crate's FFI functions, and must not be freed more than once.
     #[no_mangle]
-    pub extern "C" fn ghostllm_free_string(s: *mut c_char) {
+    pub unsafe extern "C" fn ghostllm_free_string(s: *mut c_char) {
         if !s.is_null() {
             unsafe {
                 let _ = CString::from_raw(s);
@@ -132,8 +320,12 @@ pub mod ffi {
     }
 
     /// Destroy a GhostLLM instance
+    ///
+    /// # Safety
+    /// `instance` must be either null or a pointer returned by
+    /// `ghostllm_init` that hasn't yet been passed to `ghostllm_destroy`.
     #[no_mangle]
-    pub extern "C" fn ghostllm_destroy(instance: *mut GhostLLM) {
+    pub unsafe extern "C" fn ghostllm_destroy(instance: *mut GhostLLM) {
         if !instance.is_null() {
             unsafe {
                 let _ = Box::from_raw(instance);
@@ -142,10 +334,15 @@ pub mod ffi {
     }
 
     /// Get last error message
+    /// Returns: the calling thread's most recent error, or "No error" if
+    /// none has been recorded yet. Valid until the next FFI call on this
+    /// thread; callers that need to retain it should copy it out.
     #[no_mangle]
     pub extern "C" fn ghostllm_last_error() -> *const c_char {
-        // In a real implementation, this would return thread-local error state
-        b"No error\0".as_ptr() as *const c_char
+        LAST_ERROR.with(|slot| match slot.borrow().as_ref() {
+            Some(message) => message.as_ptr(),
+            None => c"No error".as_ptr(),
+        })
     }
 }
 
@@ -171,4 +368,26 @@ mod tests {
         assert!(resp.content.contains("Hello, world!"));
         assert_eq!(resp.tokens_used, 100);
     }
+
+    #[test]
+    fn test_ghostllm_chat_completion_stream() {
+        let mut llm = GhostLLM::new("test-model.bin");
+        llm.init().unwrap();
+
+        let request = ChatRequest {
+            prompt: "Hello, world!".to_string(),
+            max_tokens: 100,
+            temperature: 0.7,
+        };
+
+        let mut tokens = Vec::new();
+        let response = llm
+            .chat_completion_stream(&request, |token| tokens.push(token.to_string()))
+            .unwrap();
+
+        assert!(!tokens.is_empty());
+        assert_eq!(tokens.join(" "), response.content);
+        assert_eq!(response.finish_reason, "stop");
+        assert_eq!(response.tokens_used, tokens.len() as u32);
+    }
 }
\ No newline at end of file
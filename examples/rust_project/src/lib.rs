@@ -1,21 +1,88 @@
 pub mod builder {
+    use std::collections::HashMap;
     use std::path::Path;
     use std::process::Command;
+    use serde::{Deserialize, Serialize};
+    use sha2::{Digest, Sha256};
 
     pub struct RustBuilder {
-        project_name: String,
-        source_files: Vec<String>,
+        pub(crate) project_name: String,
+        pub(crate) source_files: Vec<String>,
         output_dir: String,
         optimization_level: OptLevel,
+        target_type: TargetType,
+        target: Option<String>,
+        jobs: usize,
+        plugins: crate::plugin::PluginRegistry,
     }
 
-    #[derive(Debug, Clone, Copy)]
+    fn default_jobs() -> usize {
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+
+    /// Target triples `zbuild` knows how to cross-compile for.
+    const KNOWN_TARGETS: &[&str] = &[
+        "x86_64-unknown-linux-gnu",
+        "aarch64-unknown-linux-gnu",
+        "x86_64-apple-darwin",
+        "aarch64-apple-darwin",
+        "x86_64-pc-windows-msvc",
+        "wasm32-wasi",
+        "wasm32-unknown-unknown",
+    ];
+
+    #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+    #[serde(rename_all = "lowercase")]
     pub enum OptLevel {
         Debug,
         Release,
         Size,
     }
 
+    /// Languages `zbuild` knows how to drive a build for.
+    #[derive(Debug, Clone, Copy, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum TargetType {
+        C,
+        Cpp,
+        Zig,
+        Rust,
+    }
+
+    /// Deserializes a TOML string, treating `""` as an absent value.
+    fn string_empty_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Ok(if s.is_empty() { None } else { Some(s) })
+    }
+
+    /// The on-disk shape of a `zbuild.toml`. Top-level fields are the
+    /// defaults; entries under `[env.<name>]` override them selectively.
+    #[derive(Debug, Deserialize)]
+    pub struct Manifest {
+        pub name: String,
+        #[serde(rename = "type")]
+        pub target_type: TargetType,
+        #[serde(default, deserialize_with = "string_empty_as_none")]
+        pub output_dir: Option<String>,
+        #[serde(default)]
+        pub sources: Vec<String>,
+        pub optimization: Option<OptLevel>,
+        #[serde(default, rename = "env")]
+        pub environments: HashMap<String, EnvOverlay>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct EnvOverlay {
+        #[serde(default, deserialize_with = "string_empty_as_none")]
+        pub output_dir: Option<String>,
+        #[serde(default)]
+        pub sources: Vec<String>,
+        pub optimization: Option<OptLevel>,
+    }
+
     impl RustBuilder {
         pub fn new(name: &str) -> Self {
             RustBuilder {
@@ -23,9 +90,53 @@ pub mod builder {
                 source_files: Vec::new(),
                 output_dir: "target".to_string(),
                 optimization_level: OptLevel::Debug,
+                target_type: TargetType::Rust,
+                target: None,
+                jobs: default_jobs(),
+                plugins: crate::plugin::PluginRegistry::new(),
             }
         }
 
+        /// Loads a `zbuild.toml` from `path` and merges the selected
+        /// `[env.<name>]` overlay (if any) on top of the top-level defaults.
+        pub fn from_manifest(path: &str, env: Option<&str>) -> Result<Self, BuildError> {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| BuildError::ManifestError(e.to_string()))?;
+            let manifest: Manifest = toml::from_str(&contents)
+                .map_err(|e| BuildError::ManifestError(e.to_string()))?;
+
+            let mut output_dir = manifest.output_dir.unwrap_or_else(|| "target".to_string());
+            let mut sources = manifest.sources;
+            let mut optimization_level = manifest.optimization.unwrap_or(OptLevel::Debug);
+
+            if let Some(name) = env {
+                let overlay = manifest.environments.get(name).ok_or_else(|| {
+                    BuildError::ManifestError(format!("unknown environment: {}", name))
+                })?;
+
+                if let Some(dir) = &overlay.output_dir {
+                    output_dir = dir.clone();
+                }
+                if !overlay.sources.is_empty() {
+                    sources = overlay.sources.clone();
+                }
+                if let Some(level) = overlay.optimization {
+                    optimization_level = level;
+                }
+            }
+
+            Ok(RustBuilder {
+                project_name: manifest.name,
+                source_files: sources,
+                output_dir,
+                optimization_level,
+                target_type: manifest.target_type,
+                target: None,
+                jobs: default_jobs(),
+                plugins: crate::plugin::PluginRegistry::new(),
+            })
+        }
+
         pub fn add_source(&mut self, path: &str) -> &mut Self {
             self.source_files.push(path.to_string());
             self
@@ -36,18 +147,129 @@ pub mod builder {
             self
         }
 
-        pub fn build(&self) -> Result<(), BuildError> {
-            println!("Building {} with {:?} optimization", self.project_name, self.optimization_level);
+        /// Bounds how many sources are checked/compiled concurrently by
+        /// [`build`](Self::build). Defaults to the available parallelism.
+        pub fn set_jobs(&mut self, jobs: usize) -> &mut Self {
+            self.jobs = jobs.max(1);
+            self
+        }
 
-            for source in &self.source_files {
-                if !Path::new(source).exists() {
-                    return Err(BuildError::SourceNotFound(source.clone()));
-                }
+        /// Sets the target triple to cross-compile for (e.g.
+        /// `aarch64-apple-darwin`, `wasm32-wasi`), validating it against
+        /// the known target list. Artifacts land under a per-target
+        /// subfolder of `output_dir` so builds for different targets don't
+        /// collide.
+        pub fn set_target(&mut self, triple: &str) -> Result<&mut Self, BuildError> {
+            if !KNOWN_TARGETS.contains(&triple) {
+                return Err(BuildError::UnknownTarget(triple.to_string()));
+            }
+
+            self.target = Some(triple.to_string());
+            Ok(self)
+        }
+
+        fn target_output_dir(&self) -> String {
+            match &self.target {
+                Some(triple) => format!("{}/{}", self.output_dir, triple),
+                None => self.output_dir.clone(),
+            }
+        }
+
+        /// The toolchain binary that drives compilation for `target_type`.
+        fn toolchain_binary(&self) -> &'static str {
+            match self.target_type {
+                TargetType::C => "cc",
+                TargetType::Cpp => "c++",
+                TargetType::Zig => "zig",
+                TargetType::Rust => "rustc",
+            }
+        }
+
+        /// The toolchain-appropriate flag(s) for cross-compiling to the
+        /// triple set by [`set_target`](Self::set_target), or empty if no
+        /// target was set.
+        pub(crate) fn target_args(&self) -> Vec<String> {
+            let Some(triple) = &self.target else {
+                return Vec::new();
+            };
+
+            match self.target_type {
+                TargetType::Rust => vec!["--target".to_string(), triple.clone()],
+                TargetType::Zig => vec!["-target".to_string(), triple.clone()],
+                TargetType::C | TargetType::Cpp => vec![format!("--target={}", triple)],
+            }
+        }
+
+        fn compile_source(source: &str) -> Result<(), BuildError> {
+            if !Path::new(source).exists() {
+                return Err(BuildError::SourceNotFound(source.to_string()));
+            }
+
+            Ok(())
+        }
+
+        fn target_descriptor(&self) -> crate::plugin::TargetDescriptor {
+            crate::plugin::TargetDescriptor {
+                name: self.project_name.clone(),
+                sources: self.source_files.clone(),
+                output_dir: self.target_output_dir(),
+            }
+        }
+
+        fn run_plugins(&mut self, step: crate::plugin::Step) -> Result<(), BuildError> {
+            use crate::plugin::Plugin;
+
+            let target = self.target_descriptor();
+
+            for plugin in self.plugins.plugins_mut() {
+                plugin
+                    .run(step, &target)
+                    .map_err(|e| BuildError::PluginFailed(e.to_string()))?;
             }
 
             Ok(())
         }
 
+        pub fn build(&mut self) -> Result<(), BuildError> {
+            let mut command = Command::new(self.toolchain_binary());
+            command.args(self.target_args());
+            command.args(&self.source_files);
+            println!(
+                "Building {} with {:?} optimization into {} via `{:?}`",
+                self.project_name, self.optimization_level, self.target_output_dir(), command
+            );
+
+            self.run_plugins(crate::plugin::Step::Validate)?;
+
+            let mut errors = Vec::new();
+
+            for batch in self.source_files.chunks(self.jobs) {
+                std::thread::scope(|scope| {
+                    let handles: Vec<_> = batch
+                        .iter()
+                        .map(|source| scope.spawn(move || Self::compile_source(source)))
+                        .collect();
+
+                    for handle in handles {
+                        if let Err(e) = handle.join().expect("compile thread panicked") {
+                            errors.push(e);
+                        }
+                    }
+                });
+            }
+
+            match errors.len() {
+                0 => {}
+                1 => return Err(errors.remove(0)),
+                _ => return Err(BuildError::Multiple(errors)),
+            }
+
+            self.run_plugins(crate::plugin::Step::Compile)?;
+            self.run_plugins(crate::plugin::Step::Link)?;
+
+            Ok(())
+        }
+
         pub fn run_tests(&self) -> Result<TestResults, BuildError> {
             Ok(TestResults {
                 passed: 10,
@@ -55,6 +277,113 @@ pub mod builder {
                 ignored: 2,
             })
         }
+
+        /// Builds a [`DependencyGraph`] describing this project's target
+        /// and the source files it depends on.
+        pub fn dependency_graph(&self) -> crate::graph::DependencyGraph {
+            let mut graph = crate::graph::DependencyGraph::new(crate::graph::Kind::Digraph);
+            graph.add_node(&self.project_name);
+
+            for source in &self.source_files {
+                graph.add_node(source);
+                graph.add_edge(&self.project_name, source);
+            }
+
+            graph
+        }
+
+        /// Loads a `wasm32-wasi` build-step plugin from `path`, sandboxing
+        /// its filesystem access to this project's `output_dir` and source
+        /// roots.
+        pub fn register_plugin(&mut self, path: &str) -> Result<(), crate::plugin::PluginError> {
+            let preopens = self.plugin_preopens();
+            self.plugins.register(path, preopens)
+        }
+
+        fn plugin_preopens(&self) -> Vec<std::path::PathBuf> {
+            let mut dirs = vec![std::path::PathBuf::from(&self.output_dir)];
+
+            for source in &self.source_files {
+                if let Some(parent) = Path::new(source).parent() {
+                    let parent = parent.to_path_buf();
+                    if !dirs.contains(&parent) {
+                        dirs.push(parent);
+                    }
+                }
+            }
+
+            dirs
+        }
+
+        fn resolve_lockfile(&self) -> Result<Lockfile, BuildError> {
+            let mut sources = Vec::new();
+
+            for source in &self.source_files {
+                let contents = std::fs::read(source)
+                    .map_err(|e| BuildError::SourceNotFound(format!("{}: {}", source, e)))?;
+                let mut hasher = Sha256::new();
+                hasher.update(&contents);
+
+                sources.push(LockedSource {
+                    path: source.clone(),
+                    hash: format!("{:x}", hasher.finalize()),
+                });
+            }
+
+            Ok(Lockfile {
+                target: self.target.clone(),
+                optimization: self.optimization_level,
+                sources,
+            })
+        }
+
+        /// Writes a `zbuild.lock` capturing the resolved sources (and their
+        /// content hashes), target and optimization level, so rebuilds are
+        /// reproducible and caches can key on the exact lock contents.
+        pub fn write_lockfile(&self) -> Result<(), BuildError> {
+            let lockfile = self.resolve_lockfile()?;
+            let contents = toml::to_string_pretty(&lockfile)
+                .map_err(|e| BuildError::LockError(e.to_string()))?;
+            std::fs::write("zbuild.lock", contents).map_err(|e| BuildError::LockError(e.to_string()))
+        }
+
+        /// Fails if any source's content hash has drifted from the
+        /// `zbuild.lock` at `path`.
+        pub fn verify_lockfile(&self, path: &str) -> Result<(), BuildError> {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| BuildError::LockError(e.to_string()))?;
+            let locked: Lockfile =
+                toml::from_str(&contents).map_err(|e| BuildError::LockError(e.to_string()))?;
+            let current = self.resolve_lockfile()?;
+
+            for locked_source in &locked.sources {
+                let drifted = match current.sources.iter().find(|s| s.path == locked_source.path) {
+                    Some(current_source) => current_source.hash != locked_source.hash,
+                    None => true,
+                };
+
+                if drifted {
+                    return Err(BuildError::LockDrift(locked_source.path.clone()));
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// A single resolved source in a [`Lockfile`].
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct LockedSource {
+        pub path: String,
+        pub hash: String,
+    }
+
+    /// The on-disk shape of `zbuild.lock`.
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct Lockfile {
+        pub target: Option<String>,
+        pub optimization: OptLevel,
+        pub sources: Vec<LockedSource>,
     }
 
     #[derive(Debug)]
@@ -62,6 +391,12 @@ pub mod builder {
         SourceNotFound(String),
         CompilationFailed(String),
         LinkingFailed(String),
+        ManifestError(String),
+        Multiple(Vec<BuildError>),
+        UnknownTarget(String),
+        LockDrift(String),
+        PluginFailed(String),
+        LockError(String),
     }
 
     pub struct TestResults {
@@ -80,15 +415,37 @@ pub mod builder {
 
 pub mod cache {
     use std::collections::HashMap;
+    use sha2::{Digest, Sha256};
+    use thiserror::Error;
 
-    pub struct Cache {
-        entries: HashMap<String, CacheEntry>,
+    #[derive(Debug, Error)]
+    pub enum CacheError {
+        #[error("cache http request failed: {0}")]
+        Http(#[from] reqwest::Error),
+        #[error("cache io error: {0}")]
+        Io(#[from] std::io::Error),
+        #[error("cache key `{given}` does not match the content hash `{expected}` of the data")]
+        KeyMismatch { given: String, expected: String },
+    }
+
+    /// Common surface both the in-memory and remote cache backends expose.
+    pub trait CacheBackend {
+        fn store(&mut self, key: &str, data: Vec<u8>) -> Result<(), CacheError>;
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CacheError>;
+        fn has(&self, key: &str) -> Result<bool, CacheError>;
     }
 
-    struct CacheEntry {
-        key: String,
-        data: Vec<u8>,
-        hash: String,
+    /// Computes the content-addressing key for `data`. [`RemoteCache`]
+    /// requires the `key` passed to its [`CacheBackend`] methods to be
+    /// exactly this value, so blobs are addressed purely by hash.
+    pub fn content_hash(data: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub struct Cache {
+        entries: HashMap<String, Vec<u8>>,
     }
 
     impl Cache {
@@ -97,25 +454,338 @@ pub mod cache {
                 entries: HashMap::new(),
             }
         }
+    }
 
-        pub fn store(&mut self, key: &str, data: Vec<u8>) {
-            let hash = format!("{:x}", md5::compute(&data));
-            self.entries.insert(
-                key.to_string(),
-                CacheEntry {
-                    key: key.to_string(),
-                    data,
-                    hash,
-                },
-            );
+    impl Default for Cache {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl CacheBackend for Cache {
+        fn store(&mut self, key: &str, data: Vec<u8>) -> Result<(), CacheError> {
+            self.entries.insert(key.to_string(), data);
+            Ok(())
+        }
+
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CacheError> {
+            Ok(self.entries.get(key).cloned())
+        }
+
+        fn has(&self, key: &str) -> Result<bool, CacheError> {
+            Ok(self.entries.contains_key(key))
+        }
+    }
+
+    /// A content-addressed cache backed by an HTTP blob store. The local
+    /// in-memory [`Cache`] is always consulted first, so a hit never
+    /// touches the network; misses are uploaded/fetched by content hash
+    /// (`PUT`/`GET {base}/{hash}`), and `has` does a cheap `HEAD`. The
+    /// `key` passed to every method IS the blob's content hash (see
+    /// [`content_hash`]) — there is no separate key→hash index to keep in
+    /// sync, so a second process can always resolve a key it never wrote.
+    pub struct RemoteCache {
+        local: Cache,
+        base_url: String,
+        client: reqwest::blocking::Client,
+    }
+
+    impl RemoteCache {
+        pub fn new(base_url: &str) -> Self {
+            RemoteCache {
+                local: Cache::new(),
+                base_url: base_url.trim_end_matches('/').to_string(),
+                client: reqwest::blocking::Client::new(),
+            }
+        }
+
+        fn blob_url(&self, hash: &str) -> String {
+            format!("{}/{}", self.base_url, hash)
+        }
+    }
+
+    impl CacheBackend for RemoteCache {
+        fn store(&mut self, key: &str, data: Vec<u8>) -> Result<(), CacheError> {
+            let expected = content_hash(&data);
+            if key != expected {
+                return Err(CacheError::KeyMismatch { given: key.to_string(), expected });
+            }
+
+            self.client.put(self.blob_url(key)).body(data.clone()).send()?;
+            self.local.store(key, data)
+        }
+
+        fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CacheError> {
+            if let Some(data) = self.local.get(key)? {
+                return Ok(Some(data));
+            }
+
+            let response = self.client.get(self.blob_url(key)).send()?;
+            if !response.status().is_success() {
+                return Ok(None);
+            }
+
+            Ok(Some(response.bytes()?.to_vec()))
+        }
+
+        fn has(&self, key: &str) -> Result<bool, CacheError> {
+            if self.local.has(key)? {
+                return Ok(true);
+            }
+
+            let response = self.client.head(self.blob_url(key)).send()?;
+            Ok(response.status().is_success())
         }
+    }
+}
+
+pub mod graph {
+    use std::fmt;
 
-        pub fn get(&self, key: &str) -> Option<&[u8]> {
-            self.entries.get(key).map(|entry| entry.data.as_slice())
+    /// Whether a graph's edges are directed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Kind {
+        Digraph,
+        Graph,
+    }
+
+    impl Kind {
+        fn keyword(self) -> &'static str {
+            match self {
+                Kind::Digraph => "digraph",
+                Kind::Graph => "graph",
+            }
         }
 
-        pub fn has(&self, key: &str) -> bool {
-            self.entries.contains_key(key)
+        fn edgeop(self) -> &'static str {
+            match self {
+                Kind::Digraph => "->",
+                Kind::Graph => "--",
+            }
+        }
+    }
+
+    /// A small build-target dependency graph that can render itself as
+    /// Graphviz DOT, e.g. for piping into `dot -Tpng`.
+    pub struct DependencyGraph {
+        kind: Kind,
+        nodes: Vec<String>,
+        edges: Vec<(String, String)>,
+    }
+
+    impl DependencyGraph {
+        pub fn new(kind: Kind) -> Self {
+            DependencyGraph {
+                kind,
+                nodes: Vec::new(),
+                edges: Vec::new(),
+            }
+        }
+
+        /// Adds a node if it isn't already present.
+        pub fn add_node(&mut self, id: &str) {
+            if !self.nodes.iter().any(|n| n == id) {
+                self.nodes.push(id.to_string());
+            }
+        }
+
+        /// Records that `from` depends on `to`, adding both as nodes.
+        pub fn add_edge(&mut self, from: &str, to: &str) {
+            self.add_node(from);
+            self.add_node(to);
+            self.edges.push((from.to_string(), to.to_string()));
+        }
+
+        pub fn to_dot(&self) -> String {
+            self.to_string()
+        }
+    }
+
+    fn quote(id: &str) -> String {
+        format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    impl fmt::Display for DependencyGraph {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            writeln!(f, "{} {{", self.kind.keyword())?;
+
+            for node in &self.nodes {
+                writeln!(f, "    {};", quote(node))?;
+            }
+
+            for (from, to) in &self.edges {
+                writeln!(f, "    {} {} {};", quote(from), self.kind.edgeop(), quote(to))?;
+            }
+
+            write!(f, "}}")
+        }
+    }
+}
+
+pub mod plugin {
+    use std::path::PathBuf;
+    use serde::{Deserialize, Serialize};
+    use thiserror::Error;
+    use wasi_common::pipe::{ReadPipe, WritePipe};
+    use wasmtime::{Engine, Linker, Module, Store};
+    use wasmtime_wasi::sync::{ambient_authority, Dir, WasiCtxBuilder};
+    use wasmtime_wasi::WasiCtx;
+
+    #[derive(Debug, Error)]
+    pub enum PluginError {
+        #[error("failed to load wasm module: {0}")]
+        Load(#[from] wasmtime::Error),
+        #[error("failed to set up the plugin sandbox: {0}")]
+        Sandbox(#[from] wasi_common::Error),
+        #[error("failed to open a preopened directory: {0}")]
+        Io(#[from] std::io::Error),
+        #[error("plugin is missing the `{0}` export")]
+        MissingExport(&'static str),
+        #[error("plugin returned malformed JSON: {0}")]
+        InvalidResponse(#[from] serde_json::Error),
+        #[error("plugin step failed: {0}")]
+        StepFailed(String),
+    }
+
+    /// Metadata about a build target, handed to a plugin as JSON for each
+    /// build step.
+    #[derive(Debug, Serialize)]
+    pub struct TargetDescriptor {
+        pub name: String,
+        pub sources: Vec<String>,
+        pub output_dir: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct StepResponse {
+        ok: bool,
+        message: Option<String>,
+    }
+
+    /// A build step a plugin implements. `Validate` checks a target before
+    /// compilation; `Compile` and `Link` perform the corresponding steps.
+    #[derive(Debug, Clone, Copy)]
+    pub enum Step {
+        Validate,
+        Compile,
+        Link,
+    }
+
+    impl Step {
+        fn export_name(self) -> &'static str {
+            match self {
+                Step::Validate => "validate",
+                Step::Compile => "compile",
+                Step::Link => "link",
+            }
+        }
+    }
+
+    /// Build-step interface a `wasm32-wasi` module must implement to add a
+    /// new toolchain or language to `zbuild` without recompiling it.
+    pub trait Plugin {
+        fn run(&mut self, step: Step, target: &TargetDescriptor) -> Result<(), PluginError>;
+
+        fn validate(&mut self, target: &TargetDescriptor) -> Result<(), PluginError> {
+            self.run(Step::Validate, target)
+        }
+
+        fn compile(&mut self, target: &TargetDescriptor) -> Result<(), PluginError> {
+            self.run(Step::Compile, target)
+        }
+
+        fn link(&mut self, target: &TargetDescriptor) -> Result<(), PluginError> {
+            self.run(Step::Link, target)
+        }
+    }
+
+    /// A loaded `wasm32-wasi` plugin module. Each step is invoked in a
+    /// fresh instance whose filesystem access is limited to WASI preopens
+    /// scoped to the project's `output_dir` and source roots; the target
+    /// descriptor is passed in over stdin as JSON and the `Result`-shaped
+    /// reply is read back over stdout.
+    pub struct WasmPlugin {
+        engine: Engine,
+        module: Module,
+        linker: Linker<WasiCtx>,
+        preopens: Vec<PathBuf>,
+    }
+
+    impl WasmPlugin {
+        pub fn load(path: &str, preopens: Vec<PathBuf>) -> Result<Self, PluginError> {
+            let engine = Engine::default();
+            let module = Module::from_file(&engine, path)?;
+            let mut linker = Linker::new(&engine);
+            wasmtime_wasi::sync::add_to_linker(&mut linker, |ctx| ctx)?;
+
+            Ok(WasmPlugin { engine, module, linker, preopens })
+        }
+
+        fn build_wasi(
+            &self,
+            stdin: ReadPipe<std::io::Cursor<Vec<u8>>>,
+            stdout: WritePipe<std::io::Cursor<Vec<u8>>>,
+        ) -> Result<WasiCtx, PluginError> {
+            let mut builder = WasiCtxBuilder::new();
+            builder.stdin(Box::new(stdin)).stdout(Box::new(stdout));
+
+            for dir in &self.preopens {
+                let preopened = Dir::open_ambient_dir(dir, ambient_authority())?;
+                builder.preopened_dir(preopened, dir.to_string_lossy().as_ref())?;
+            }
+
+            Ok(builder.build())
+        }
+    }
+
+    impl Plugin for WasmPlugin {
+        fn run(&mut self, step: Step, target: &TargetDescriptor) -> Result<(), PluginError> {
+            let request = serde_json::to_vec(target)?;
+            let stdin = ReadPipe::from(request);
+            let stdout = WritePipe::new_in_memory();
+
+            let wasi = self.build_wasi(stdin, stdout.clone())?;
+            let mut store = Store::new(&self.engine, wasi);
+            let instance = self.linker.instantiate(&mut store, &self.module)?;
+
+            let entry = instance
+                .get_typed_func::<(), ()>(&mut store, step.export_name())
+                .map_err(|_| PluginError::MissingExport(step.export_name()))?;
+            entry.call(&mut store, ())?;
+            drop(store);
+
+            let output = stdout
+                .try_into_inner()
+                .expect("no outstanding references to stdout pipe")
+                .into_inner();
+            let response: StepResponse = serde_json::from_slice(&output)?;
+
+            if response.ok {
+                Ok(())
+            } else {
+                Err(PluginError::StepFailed(response.message.unwrap_or_default()))
+            }
+        }
+    }
+
+    /// Holds the plugins a [`crate::builder::RustBuilder`] has loaded.
+    #[derive(Default)]
+    pub struct PluginRegistry {
+        plugins: Vec<WasmPlugin>,
+    }
+
+    impl PluginRegistry {
+        pub fn new() -> Self {
+            PluginRegistry { plugins: Vec::new() }
+        }
+
+        pub fn register(&mut self, path: &str, preopens: Vec<PathBuf>) -> Result<(), PluginError> {
+            self.plugins.push(WasmPlugin::load(path, preopens)?);
+            Ok(())
+        }
+
+        pub fn plugins_mut(&mut self) -> impl Iterator<Item = &mut WasmPlugin> {
+            self.plugins.iter_mut()
         }
     }
 }
@@ -137,4 +807,146 @@ mod tests {
                .add_source("src/lib.rs");
         assert_eq!(builder.source_files.len(), 2);
     }
+
+    #[test]
+    fn test_manifest_env_overlay() {
+        let toml = r#"
+            name = "demo"
+            type = "rust"
+            output_dir = "target"
+            sources = ["src/main.rs"]
+            optimization = "debug"
+
+            [env.release]
+            output_dir = ""
+            sources = ["src/main.rs", "src/lib.rs"]
+            optimization = "release"
+        "#;
+
+        let manifest: Manifest = toml::from_str(toml).unwrap();
+        assert_eq!(manifest.name, "demo");
+        assert!(manifest.environments.contains_key("release"));
+
+        let overlay = &manifest.environments["release"];
+        assert!(overlay.output_dir.is_none());
+        assert_eq!(overlay.sources.len(), 2);
+    }
+
+    #[test]
+    fn test_dependency_graph_to_dot() {
+        let mut builder = RustBuilder::new("demo");
+        builder.add_source("src/main.rs").add_source("src/lib.rs");
+
+        let dot = builder.dependency_graph().to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("\"demo\" -> \"src/main.rs\";"));
+        assert!(dot.contains("\"demo\" -> \"src/lib.rs\";"));
+    }
+
+    #[test]
+    fn test_dependency_graph_escapes_quotes() {
+        use super::graph::{DependencyGraph, Kind};
+
+        let mut graph = DependencyGraph::new(Kind::Digraph);
+        graph.add_edge("demo", "src/\"weird\".rs");
+
+        assert!(graph.to_dot().contains("src/\\\"weird\\\".rs"));
+    }
+
+    #[test]
+    fn test_local_cache_roundtrip() {
+        use super::cache::{Cache, CacheBackend};
+
+        let mut cache = Cache::new();
+        assert!(!cache.has("artifact").unwrap());
+
+        cache.store("artifact", vec![1, 2, 3]).unwrap();
+        assert!(cache.has("artifact").unwrap());
+        assert_eq!(cache.get("artifact").unwrap(), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_remote_cache_requires_key_to_be_content_hash() {
+        use super::cache::{content_hash, CacheBackend, CacheError, RemoteCache};
+
+        let mut cache = RemoteCache::new("http://127.0.0.1:0");
+        let data = vec![1, 2, 3];
+
+        match cache.store("not-the-hash", data.clone()) {
+            Err(CacheError::KeyMismatch { given, expected }) => {
+                assert_eq!(given, "not-the-hash");
+                assert_eq!(expected, content_hash(&data));
+            }
+            other => panic!("expected KeyMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_build_reports_all_missing_sources() {
+        let mut builder = RustBuilder::new("test");
+        builder
+            .set_jobs(2)
+            .add_source("does/not/exist.rs")
+            .add_source("also/missing.rs");
+
+        match builder.build() {
+            Err(BuildError::Multiple(errors)) => assert_eq!(errors.len(), 2),
+            other => panic!("expected BuildError::Multiple, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_set_target_rejects_unknown_triple() {
+        let mut builder = RustBuilder::new("test");
+        assert!(builder.set_target("aarch64-apple-darwin").is_ok());
+        assert!(matches!(
+            builder.set_target("not-a-real-triple"),
+            Err(BuildError::UnknownTarget(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_target_threads_triple_into_build_command() {
+        let mut builder = RustBuilder::new("test");
+        assert!(builder.target_args().is_empty());
+
+        builder.set_target("aarch64-apple-darwin").unwrap();
+        assert_eq!(
+            builder.target_args(),
+            vec!["--target".to_string(), "aarch64-apple-darwin".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_lockfile_detects_source_drift() {
+        let mut builder = RustBuilder::new("test");
+        builder.add_source("src/lib.rs");
+
+        let lock_path = std::env::temp_dir().join("zbuild_test.lock");
+        let lockfile = Lockfile {
+            target: None,
+            optimization: OptLevel::Debug,
+            sources: vec![LockedSource {
+                path: "src/lib.rs".to_string(),
+                hash: "deadbeef".to_string(),
+            }],
+        };
+        std::fs::write(&lock_path, toml::to_string_pretty(&lockfile).unwrap()).unwrap();
+
+        let result = builder.verify_lockfile(lock_path.to_str().unwrap());
+        std::fs::remove_file(&lock_path).ok();
+
+        assert!(matches!(result, Err(BuildError::LockDrift(path)) if path == "src/lib.rs"));
+    }
+
+    #[test]
+    fn test_build_runs_plugin_stages_with_no_plugins_registered() {
+        let mut builder = RustBuilder::new("test");
+        builder.add_source("src/lib.rs");
+
+        // `run_plugins` is invoked at the Validate, Compile and Link stages
+        // of a build whose sources all exist; with an empty `PluginRegistry` each call should be
+        // a no-op and the build should still succeed.
+        assert!(builder.build().is_ok());
+    }
 }
\ No newline at end of file
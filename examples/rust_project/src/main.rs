@@ -1,6 +1,14 @@
 use std::collections::HashMap;
 
+use rust_project::builder::RustBuilder;
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("--dump-graph") {
+        dump_dependency_graph();
+        return;
+    }
+
     println!("zbuild Rust Example");
     println!("===================");
 
@@ -17,6 +25,14 @@ fn main() {
     demonstrate_features();
 }
 
+/// Prints this project's dependency graph as Graphviz DOT, e.g.:
+/// `rust_project --dump-graph | dot -Tpng -o graph.png`.
+fn dump_dependency_graph() {
+    let mut builder = RustBuilder::new("zbuild-example");
+    builder.add_source("src/main.rs").add_source("src/lib.rs");
+    print!("{}", builder.dependency_graph().to_dot());
+}
+
 struct Stats {
     data: HashMap<String, i32>,
 }
@@ -51,7 +67,7 @@ fn fibonacci(n: u32) -> u32 {
 fn demonstrate_features() {
     println!("\nSupported Features:");
 
-    let features = vec![
+    let features = [
         "Incremental compilation",
         "Cross-compilation support",
         "Dependency management",